@@ -0,0 +1,42 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use fast_hampath::fastpath;
+use fast_hampath::fastpath_naive;
+use fast_hampath::tngraph::TournamentGraph;
+
+// Graph construction is O(n^2) and swamps the solve at these sizes, so it's
+// generated fresh per iteration in `iter_batched`'s untimed setup closure
+// rather than inside the timed routine, to actually compare the solvers
+// instead of mostly measuring setup.
+
+pub fn benchmark_fastpath_1000(c: &mut Criterion) {
+    c.bench_function("fastpath n=1000", |b| {
+        b.iter_batched(|| TournamentGraph::new_random(black_box(1000)), |g| fastpath::solve(&g), BatchSize::LargeInput)
+    });
+}
+
+pub fn benchmark_slowpath_1000(c: &mut Criterion) {
+    c.bench_function("slowpath n=1000", |b| {
+        b.iter_batched(|| TournamentGraph::new_random(black_box(1000)), |g| fastpath_naive::solve_path(&g), BatchSize::LargeInput)
+    });
+}
+
+pub fn benchmark_fastpath_5000(c: &mut Criterion) {
+    c.bench_function("fastpath n=5000", |b| {
+        b.iter_batched(|| TournamentGraph::new_random(black_box(5000)), |g| fastpath::solve(&g), BatchSize::LargeInput)
+    });
+}
+
+pub fn benchmark_slowpath_5000(c: &mut Criterion) {
+    c.bench_function("slowpath n=5000", |b| {
+        b.iter_batched(|| TournamentGraph::new_random(black_box(5000)), |g| fastpath_naive::solve_path(&g), BatchSize::LargeInput)
+    });
+}
+
+criterion_group!(
+    compare_benches,
+    benchmark_fastpath_1000,
+    benchmark_slowpath_1000,
+    benchmark_fastpath_5000,
+    benchmark_slowpath_5000
+);
+criterion_main!(compare_benches);
@@ -1,7 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use fast_hampath::slowpath::HampathBuilder;
-use fast_hampath::tngraph::TournamentGraph;
-use typed_arena::Arena;
+use fast_hampath::fastpath_naive::HampathBuilder;
 
 pub fn benchmark_100(c: &mut Criterion) {
     c.bench_function("solve n=100", |b| {
@@ -28,9 +26,7 @@ pub fn benchmark_10(c: &mut Criterion) {
 }
 
 fn solve_random_hampath(n: usize) {
-    let a = Arena::new();
-    let g = TournamentGraph::new_random(n, &a);
-    HampathBuilder::new(&g).solve();
+    HampathBuilder::new_random(n).solve_path();
 }
 
 fn sleep_short_time(c: &mut Criterion) {
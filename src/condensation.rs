@@ -0,0 +1,95 @@
+/*! The strongly connected components of a tournament are contiguous blocks
+ * along its (unique, up to internal reordering) Hamiltonian path, and the
+ * condensation -- the SCCs contracted to single nodes -- is itself a
+ * transitive tournament: a total order of components where every earlier
+ * component beats every later one. This pairs naturally with `hamcycle`:
+ * a tournament condenses to a single component exactly when it's strongly
+ * connected, which is the precondition for `solve_cycle` to succeed. */
+
+use crate::fastpath;
+use crate::tngraph::{TournamentGraph, NodeID};
+
+/// Splits a solved Hamiltonian path `v_1 .. v_n` into its contiguous SCCs.
+///
+/// A cut is valid right after position `m` iff `{v_1..v_m}` dominates
+/// `{v_{m+1}..v_n}`, i.e. no vertex in the suffix has an edge back into the
+/// prefix. We compute, for every position `i`, the furthest-right position
+/// of any vertex with an edge back into `v_i` (0 if none), then scan left
+/// to right keeping a running max of that value: a cut is valid wherever
+/// the running max hasn't yet exceeded the current position. O(n^2), from
+/// the all-pairs back-edge scan; each pair is an O(1) `TournamentGraph::
+/// contains` check, not an O(degree) `neighbor_ids().contains()` scan.
+pub fn condensation(graph: &TournamentGraph, path: &[NodeID]) -> Vec<Vec<NodeID>> {
+    let n = path.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut furthest_back_target = vec![0usize; n];
+    for j in 0..n {
+        for i in 0..j {
+            if graph.contains(path[j], path[i]) {
+                furthest_back_target[i] = furthest_back_target[i].max(j);
+            }
+        }
+    }
+
+    let mut components = Vec::new();
+    let mut start = 0;
+    let mut running_max = 0;
+    for (m, &target) in furthest_back_target.iter().enumerate() {
+        running_max = running_max.max(target);
+        if running_max <= m {
+            components.push(path[start..=m].to_vec());
+            start = m + 1;
+        }
+    }
+
+    components
+}
+
+/// Solves a Hamiltonian path for `graph` and returns its SCCs, in
+/// dominance order (each component beats every component after it).
+pub fn strongly_connected_components(graph: &TournamentGraph) -> Vec<Vec<NodeID>> {
+    let path = fastpath::solve(graph);
+    condensation(graph, &path)
+}
+
+pub fn is_strongly_connected(graph: &TournamentGraph) -> bool {
+    strongly_connected_components(graph).len() <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transitive_tournament_condenses_to_singletons() {
+        let edges = vec![(0, 1), (1, 2), (0, 2)];
+        let graph = TournamentGraph::new_unchecked(3, edges);
+        let sccs = strongly_connected_components(&graph);
+        assert_eq!(sccs, vec![vec![0], vec![1], vec![2]]);
+        assert!(!is_strongly_connected(&graph));
+    }
+
+    #[test]
+    fn three_cycle_is_one_component() {
+        let edges = vec![(0, 1), (1, 2), (2, 0)];
+        let graph = TournamentGraph::new_unchecked(3, edges);
+        let sccs = strongly_connected_components(&graph);
+        assert_eq!(sccs.len(), 1);
+        assert_eq!(sccs[0].len(), 3);
+        assert!(is_strongly_connected(&graph));
+    }
+
+    #[test]
+    fn condensation_covers_every_vertex_exactly_once() {
+        for _ in 0..100 {
+            let graph = TournamentGraph::new_random(40);
+            let sccs = strongly_connected_components(&graph);
+            let mut seen = sccs.iter().flatten().cloned().collect::<Vec<_>>();
+            seen.sort();
+            assert_eq!(seen, (0..40).collect::<Vec<_>>());
+        }
+    }
+}
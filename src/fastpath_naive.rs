@@ -1,76 +1,119 @@
-/*! "Naive" fast Hamiltonian path: do the search breakup, but without intelligent
- * searching for "switching" locations on the interior. */
+/*! "Naive" fast Hamiltonian path: extend the path one node at a time, same as
+ * `fastpath` used to, but binary-search the insertion point instead of
+ * scanning the whole path on every insert.
+ *
+ * `PLinkedList` is now a balanced order-maintenance structure (an implicit
+ * treap -- see its module doc), giving O(log n) expected indexed access and
+ * insertion. The insertion-point search itself is a single O(log n)
+ * expected descent of that same tree (`PLinkedList::rightmost_where`),
+ * rather than a position-based binary search that calls `cur_path.get` (its
+ * own O(log n) descent) at each of its O(log n) steps. Combined with the
+ * O(1) edge test (`TournamentGraph::contains`), each insert is O(log n)
+ * expected, for O(n log n) overall. */
 
-use crate::tngraph::{TournamentGraph, Node, NodeID};
+use crate::tngraph::{TournamentGraph, NodeID};
 use crate::perm_ll::PLinkedList;
-use typed_arena::Arena;
 
-/* General strategy: 
+/* General strategy:
 
 */
 
-pub struct HampathBuilder<'a> {
-    num_nodes: usize,  // The number of nodes in a completed path
-    last_node: usize,  // The last node to appear in the current path
-    cur_path: PLinkedList,
-    graph: TournamentGraph<'a>,
-    arena: &'a Arena<Node<'a>>,
+pub struct HampathBuilder {
+    graph: TournamentGraph,
 }
 
-impl<'a> HampathBuilder<'a> {
-    pub fn new_random(n: usize, arena: &'a Arena<Node<'a>>) -> Self {
-        let graph = TournamentGraph::new_random(n, &arena);
-        Self {
-            num_nodes: n,
-            last_node: 0,
-            cur_path: PLinkedList::new(n, 0),
-            graph,
-            arena: &arena
-        }
+impl HampathBuilder {
+    pub fn new(n: usize, edges: Vec<(NodeID, NodeID)>) -> Self {
+        let graph = TournamentGraph::new(n, edges).expect("Invalid edge array in construction!");
+        Self { graph }
+    }
+
+    pub fn new_random(n: usize) -> Self {
+        let graph = TournamentGraph::new_random(n);
+        Self { graph }
     }
 
-    pub fn into_graph(self) -> TournamentGraph<'a> {
+    pub fn into_graph(self) -> TournamentGraph {
         self.graph
     }
 
-    pub fn solve_path(&mut self) -> Vec<NodeID> {
-        while self.last_node < self.num_nodes-1 {
-            println!("{}", self.last_node);
-            self.extend()
-        }
-        self.cur_path.iter().collect::<Vec<_>>()
+    pub fn solve_path(&self) -> Vec<NodeID> {
+        solve_path(&self.graph)
     }
+}
+
+/// Computes a Hamiltonian path of `graph` in O(n log n) expected: extend a
+/// path one node at a time, in node-ID order (so the path is always built
+/// from a prefix of the graph's vertices), binary-searching for each
+/// insertion point.
+pub fn solve_path(graph: &TournamentGraph) -> Vec<NodeID> {
+    let num_nodes = graph.len();
+    let mut cur_path = PLinkedList::new(num_nodes, 0);
+    let mut last_node = 0;
+    while last_node < num_nodes - 1 {
+        extend(graph, &mut cur_path, &mut last_node, num_nodes);
+    }
+    cur_path.iter().collect::<Vec<_>>()
+}
 
-    /// Extends the path by adding the next unknown node into the cur_path list 
-    fn extend(&mut self) {
-        let new_nid = self.last_node + 1;
-        let first_nid = self.cur_path.first();
-        let last_nid = self.cur_path.last();
-        assert!(new_nid < self.num_nodes, "Tried to extend to node {} in a {} graph", new_nid, self.num_nodes);
+/// Finds a node `v` in `cur_path` such that `v -> u` and `u -> (the
+/// node right after v)`, returning `v`. Only called once the
+/// prepend/append cases are ruled out, so we know `path.first() -> u`
+/// and `u -> path.last()` both hold, i.e. `graph.contains(_, u)` is true
+/// at the first path position and false at the last: exactly the
+/// endpoint guarantee `rightmost_where` needs (it doesn't require the
+/// predicate to be monotonic in between -- see its doc comment), folded
+/// into one O(log n) expected tree descent instead of a position-based
+/// binary search that would call `cur_path.get` (itself O(log n)) at
+/// each of its O(log n) steps.
+fn search_for_insert_point(graph: &TournamentGraph, cur_path: &PLinkedList, u: NodeID) -> NodeID {
+    cur_path.rightmost_where(|v| graph.contains(v, u))
+}
 
-        // Increment last_node here to avoid having to duplicate it below, but
-        // note that last_node is *incorrect* until this function finishes
-        self.last_node += 1;
+/// Extends the path by adding the next unknown node into the cur_path list
+fn extend(graph: &TournamentGraph, cur_path: &mut PLinkedList, last_node: &mut usize, num_nodes: usize) {
+    let new_nid = *last_node + 1;
+    let first_nid = cur_path.first();
+    let last_nid = cur_path.last();
+    assert!(new_nid < num_nodes, "Tried to extend to node {} in a {} graph", new_nid, num_nodes);
 
+    // Increment last_node here to avoid having to duplicate it below, but
+    // note that last_node is *incorrect* until this function finishes
+    *last_node += 1;
 
-        // Easy case: if path from new node to first node, prepend
-        if self.graph.get_node(new_nid).neighbor_ids().contains(&first_nid){
-            self.cur_path.insert_at_start(new_nid);
-            return;
-        }
+    // Easy case: if path from new node to first node, prepend
+    if graph.contains(new_nid, first_nid) {
+        cur_path.insert_at_start(new_nid);
+        return;
+    }
 
-        // Easy case: if path from last node to new node, append
-        if self.graph.get_node(last_nid).neighbor_ids().contains(&new_nid){
-            self.cur_path.insert_at_end(new_nid);
-        }
+    // Easy case: if path from last node to new node, append
+    if graph.contains(last_nid, new_nid) {
+        cur_path.insert_at_end(new_nid);
+        return;
+    }
+
+    // Tricky case: binary-search for the insertion point
+    let i_id = search_for_insert_point(graph, cur_path, new_nid);
+    cur_path.insert_after(i_id, new_nid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Tricky case: have to search list for new nodes
-        let neighs = self.graph.get_node(new_nid).neighbor_ids();
-        let path = self.cur_path.iter().skip(1).collect::<Vec<_>>();
-        for i in path.into_iter() {
-            if neighs.contains(&i){
-                self.cur_path.insert_after(i, new_nid);
-            }
+    #[test]
+    fn test_randomized_hampath_solve() {
+        for n in [2, 3, 5, 10, 50, 200, 500] {
+            let b = HampathBuilder::new_random(n);
+            let path = b.solve_path();
+            assert!(
+                b.graph.validate_path(&path[..]),
+                "Path {:?} is invalid for graph (n={}):\n{}",
+                path,
+                n,
+                b.graph
+            );
         }
     }
 }
\ No newline at end of file
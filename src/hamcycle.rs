@@ -0,0 +1,263 @@
+/*! Hamiltonian cycle construction for strongly connected tournaments, via
+ * Camion's theorem: every strongly connected tournament has a Hamiltonian
+ * cycle. The construction is:
+ *
+ *   1. Find any 3-cycle to seed a ring.
+ *   2. While the ring doesn't span all n vertices, extend it by one of two
+ *      splice moves. If some off-ring vertex `u` has both an in-neighbor
+ *      and an out-neighbor on the ring, splice it in between them.
+ *      Otherwise every off-ring vertex either dominates the whole ring
+ *      (set `A`) or is dominated by the whole ring (set `B`); strong
+ *      connectivity then guarantees some `b` in `B` beats some `a` in `A`
+ *      (otherwise `A` would dominate everything outside itself, with no
+ *      way back in), so splicing the pair `b, a` in between *any* two
+ *      consecutive ring vertices `c_i, c_{i+1}` works, since `c_i -> b` and
+ *      `a -> c_{i+1}` hold for every ring vertex.
+ *      Camion's lemma guarantees one of these two moves always applies as
+ *      long as the tournament is strongly connected and the ring isn't yet
+ *      everything.
+ *
+ * If no 3-cycle exists, or neither splice move is available before the ring
+ * covers all vertices, the tournament isn't strongly connected and there is
+ * no Hamiltonian cycle. */
+
+use crate::tngraph::{TournamentGraph, NodeID};
+
+pub struct CycleBuilder {
+    graph: TournamentGraph,
+}
+
+impl CycleBuilder {
+    pub fn new(n: usize, edges: Vec<(NodeID, NodeID)>) -> Self {
+        let graph = TournamentGraph::new(n, edges).expect("Invalid edge array in construction!");
+        Self { graph }
+    }
+
+    pub fn new_random(n: usize) -> Self {
+        let graph = TournamentGraph::new_random(n);
+        Self { graph }
+    }
+
+    pub fn into_graph(self) -> TournamentGraph {
+        self.graph
+    }
+
+    /// Returns a Hamiltonian cycle, or `None` if `graph` is not strongly
+    /// connected.
+    pub fn solve_cycle(&self) -> Option<Vec<NodeID>> {
+        solve_cycle(&self.graph)
+    }
+}
+
+/// Finds a 3-cycle `x -> a -> b -> x` to seed the ring: split the rest of
+/// the vertices into `x`'s out-neighbors `A` and in-neighbors `B`, then
+/// look for an edge `a -> b` with `a` in `A`, `b` in `B`. Such an edge
+/// exists iff the tournament is strongly connected (otherwise `x`'s
+/// out-neighbors would never reach back to its in-neighbors).
+fn find_seed_triangle(graph: &TournamentGraph, x: NodeID) -> Option<(NodeID, NodeID)> {
+    let mut out_of_x = Vec::new();
+    let mut into_x = Vec::new();
+    for v in 0..graph.len() {
+        if v == x {
+            continue;
+        }
+        if graph.contains(x, v) {
+            out_of_x.push(v);
+        } else {
+            into_x.push(v);
+        }
+    }
+
+    for &a in &out_of_x {
+        for &b in &into_x {
+            if graph.contains(a, b) {
+                return Some((a, b));
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds an off-ring vertex with both an in-neighbor and an out-neighbor on
+/// `ring` (`has_in`/`has_out`, incrementally maintained by the caller so
+/// this doesn't have to recompute a vertex's ring-adjacency from scratch),
+/// and the ring position right after that in-neighbor to splice it into:
+/// O(n) to find the vertex, O(ring.len()) to locate the splice position,
+/// for O(n) per call overall.
+fn find_splice_point(
+    graph: &TournamentGraph,
+    ring: &[NodeID],
+    on_ring: &[bool],
+    has_in: &[bool],
+    has_out: &[bool],
+) -> Option<(usize, NodeID)> {
+    let u = (0..on_ring.len()).find(|&v| !on_ring[v] && has_in[v] && has_out[v])?;
+    for i in 0..ring.len() {
+        let c_i = ring[i];
+        let c_next = ring[(i + 1) % ring.len()];
+        if graph.contains(c_i, u) && graph.contains(u, c_next) {
+            return Some((i + 1, u));
+        }
+    }
+    None
+}
+
+/// Finds a dominator/dominated pair to splice in when no single vertex has
+/// both an in- and out-neighbor on the ring (see the module doc, case b):
+/// `A` is the off-ring vertices that beat every ring vertex, `B` the
+/// off-ring vertices that lose to every ring vertex -- every off-ring
+/// vertex is in exactly one of the two once case (a) has been ruled out
+/// (it can't have neither relationship to a nonempty ring, and having both
+/// is exactly case (a)), so this only has to search for a `B -> A` edge
+/// between them. O(|A| * |B|),
+/// which is O(n^2) worst case but only reached when the simple splice case
+/// fails.
+fn find_pair_splice_point(
+    graph: &TournamentGraph,
+    on_ring: &[bool],
+    has_in: &[bool],
+    has_out: &[bool],
+) -> Option<(NodeID, NodeID)> {
+    let dominates_ring: Vec<NodeID> = (0..on_ring.len())
+        .filter(|&v| !on_ring[v] && has_out[v] && !has_in[v])
+        .collect();
+    let dominated_by_ring: Vec<NodeID> = (0..on_ring.len())
+        .filter(|&v| !on_ring[v] && has_in[v] && !has_out[v])
+        .collect();
+
+    for &b in &dominated_by_ring {
+        for &a in &dominates_ring {
+            if graph.contains(b, a) {
+                return Some((b, a));
+            }
+        }
+    }
+    None
+}
+
+/// Updates `has_in`/`has_out` for every off-ring vertex now that `joined`
+/// has been added to the ring: O(n).
+fn record_ring_adjacency(graph: &TournamentGraph, on_ring: &[bool], joined: NodeID, has_in: &mut [bool], has_out: &mut [bool]) {
+    for (v, &is_on_ring) in on_ring.iter().enumerate() {
+        if is_on_ring {
+            continue;
+        }
+        if graph.contains(joined, v) {
+            has_in[v] = true;
+        }
+        if graph.contains(v, joined) {
+            has_out[v] = true;
+        }
+    }
+}
+
+/// Computes a Hamiltonian cycle of `graph` in O(n^2), or `None` if `graph`
+/// is not strongly connected.
+pub fn solve_cycle(graph: &TournamentGraph) -> Option<Vec<NodeID>> {
+    let n = graph.len();
+    if n < 3 {
+        return None;
+    }
+
+    let (a, b) = find_seed_triangle(graph, 0)?;
+    let mut ring = vec![0, a, b];
+    let mut on_ring = vec![false; n];
+    on_ring[0] = true;
+    on_ring[a] = true;
+    on_ring[b] = true;
+
+    // has_in[v]/has_out[v]: whether off-ring vertex v currently has an
+    // in-/out-neighbor among `ring`'s vertices. Maintaining these
+    // incrementally (O(n) per ring join) is what keeps `find_splice_point`
+    // down to O(n) per call instead of recomputing each candidate's
+    // ring-adjacency by rescanning the whole ring every time.
+    let mut has_in = vec![false; n];
+    let mut has_out = vec![false; n];
+    for &seed in &ring {
+        record_ring_adjacency(graph, &on_ring, seed, &mut has_in, &mut has_out);
+    }
+
+    while ring.len() < n {
+        if let Some((insert_at, u)) = find_splice_point(graph, &ring, &on_ring, &has_in, &has_out) {
+            ring.insert(insert_at, u);
+            on_ring[u] = true;
+            record_ring_adjacency(graph, &on_ring, u, &mut has_in, &mut has_out);
+            continue;
+        }
+
+        let (b, a) = find_pair_splice_point(graph, &on_ring, &has_in, &has_out)?;
+        ring.insert(1, b);
+        ring.insert(2, a);
+        on_ring[b] = true;
+        on_ring[a] = true;
+        record_ring_adjacency(graph, &on_ring, b, &mut has_in, &mut has_out);
+        record_ring_adjacency(graph, &on_ring, a, &mut has_in, &mut has_out);
+    }
+
+    Some(ring)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::condensation;
+
+    #[test]
+    fn strongly_connected_triangle_has_cycle() {
+        let edges = vec![(0, 1), (1, 2), (2, 0)];
+        let b = CycleBuilder::new(3, edges);
+        let cycle = b.solve_cycle().expect("a 3-cycle is strongly connected");
+        assert!(b.into_graph().validate_cycle(&cycle));
+    }
+
+    #[test]
+    fn transitive_tournament_has_no_cycle() {
+        // 0 -> 1 -> 2, 0 -> 2: a transitive (non-strongly-connected)
+        // tournament has no Hamiltonian cycle.
+        let edges = vec![(0, 1), (1, 2), (0, 2)];
+        let b = CycleBuilder::new(3, edges);
+        assert!(b.solve_cycle().is_none());
+    }
+
+    /// Regression test for a false negative: the ring gets stuck at
+    /// `[0, 3, 1, 4]`, where vertex 2 is dominated by the whole ring and
+    /// vertex 5 dominates the whole ring -- `2 -> 5` is exactly the
+    /// dominator/dominated pair `find_splice_point` alone can't splice, but
+    /// the graph (confirmed independently by `condensation::
+    /// is_strongly_connected`) is strongly connected.
+    #[test]
+    fn strongly_connected_needs_pair_splice() {
+        let edges = vec![
+            (0,1),(0,2),(0,3),(1,2),(1,4),(2,5),(3,1),(3,2),
+            (4,0),(4,2),(4,3),(5,0),(5,1),(5,3),(5,4),
+        ];
+        let b = CycleBuilder::new(6, edges);
+        let cycle = b.solve_cycle().expect("this tournament is strongly connected");
+        assert!(b.into_graph().validate_cycle(&cycle));
+    }
+
+    #[test]
+    fn randomized_strongly_connected_cycles_validate() {
+        // Not every random tournament is strongly connected: check that a
+        // returned cycle is valid, and -- since a missed splice case could
+        // otherwise make solve_cycle wrongly return None, as
+        // strongly_connected_needs_pair_splice above caught -- that
+        // solve_cycle's None/Some verdict agrees with the independently
+        // computed condensation::is_strongly_connected on every trial.
+        for _ in 0..100 {
+            let b = CycleBuilder::new_random(30);
+            let graph = b.into_graph();
+            let cycle = solve_cycle(&graph);
+            assert_eq!(
+                cycle.is_some(),
+                condensation::is_strongly_connected(&graph),
+                "solve_cycle disagrees with condensation::is_strongly_connected for graph:\n{}",
+                graph
+            );
+            if let Some(cycle) = cycle {
+                assert!(graph.validate_cycle(&cycle), "Cycle {:?} is invalid for graph:\n{}", cycle, graph);
+            }
+        }
+    }
+}
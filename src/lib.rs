@@ -0,0 +1,6 @@
+pub mod tngraph;
+pub mod perm_ll;
+pub mod fastpath;
+pub mod fastpath_naive;
+pub mod hamcycle;
+pub mod condensation;
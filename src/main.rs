@@ -1,13 +1,9 @@
-use fast_hampath::slowpath::HampathBuilder;
-use typed_arena::Arena;
-use fast_hampath::tngraph::TournamentGraph;
+use fast_hampath::fastpath_naive::HampathBuilder;
 
 fn main() {
-    for _ in 0..100{
-        let arena = Arena::new();
-        let graph = TournamentGraph::new_random(100, &arena);
-        let mut builder = HampathBuilder::new(&graph);
+    for _ in 0..100 {
+        let builder = HampathBuilder::new_random(100);
 
-        let path = builder.solve();
+        let _path = builder.solve_path();
     }
-}
\ No newline at end of file
+}
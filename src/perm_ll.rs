@@ -4,72 +4,261 @@
  * slightly better: we know that our final data structure will contain a permutation
  * of the numbers 0..N, where N is the size of the graph. We can use this to
  * construct a structure which supports the important linked list operations.
- */
+ *
+ * To also support indexed access (needed for binary-searching the insertion
+ * point in O(log n) comparisons instead of a linear scan), the permutation is
+ * kept as an implicit treap -- a randomly-balanced binary search tree keyed
+ * purely by in-order position, with each node storing the size of its
+ * subtree. `get(idx)` descends the tree using subtree sizes to find the
+ * `idx`-th element; inserting at a position `split`s the tree there and
+ * `merge`s the new node back in. Both are O(log n) expected, since treap
+ * priorities are random, giving the balanced order-maintenance structure a
+ * sqrt(n)-block scheme could only approximate.
+ *
+ * `insert_after(cur, next)` needs `cur`'s current position, so `handle_of`
+ * maps each node id directly to its tree node, and `rank` walks from that
+ * node up to the root, summing left-subtree sizes past every right-turn --
+ * also O(log n) expected, with no scan over node *values*. */
+
+use rand::random;
+
+struct TreapNode {
+    value: usize,
+    priority: u64,
+    left: Option<usize>,
+    right: Option<usize>,
+    parent: Option<usize>,
+    size: usize,
+}
 
 /// A Permutation Linked List
 pub struct PLinkedList {
-    first: usize,
-    last: usize,
-    links: Vec<Option<usize>>
+    nodes: Vec<TreapNode>,
+    root: Option<usize>,
+    /// `handle_of[node]` is the index into `nodes` holding `node`.
+    handle_of: Vec<usize>,
 }
 
 impl PLinkedList {
     pub fn new(length: usize, first: usize) -> Self {
-        Self {
-            first,
-            last: first,
-            links: vec![None; length]
+        let mut pll = Self {
+            nodes: Vec::with_capacity(length),
+            root: None,
+            handle_of: vec![usize::MAX; length],
+        };
+        let h = pll.alloc(first);
+        pll.root = Some(h);
+        pll
+    }
+
+    fn alloc(&mut self, value: usize) -> usize {
+        let handle = self.nodes.len();
+        self.nodes.push(TreapNode {
+            value,
+            priority: random(),
+            left: None,
+            right: None,
+            parent: None,
+            size: 1,
+        });
+        self.handle_of[value] = handle;
+        handle
+    }
+
+    fn size(&self, h: Option<usize>) -> usize {
+        h.map_or(0, |h| self.nodes[h].size)
+    }
+
+    fn update_size(&mut self, h: usize) {
+        let size = 1 + self.size(self.nodes[h].left) + self.size(self.nodes[h].right);
+        self.nodes[h].size = size;
+    }
+
+    fn set_left(&mut self, h: usize, left: Option<usize>) {
+        self.nodes[h].left = left;
+        if let Some(left) = left {
+            self.nodes[left].parent = Some(h);
         }
+        self.update_size(h);
     }
-    
-    pub fn get_succ(&self, cur: usize) -> Option<usize> {
-        self.links[cur]
+
+    fn set_right(&mut self, h: usize, right: Option<usize>) {
+        self.nodes[h].right = right;
+        if let Some(right) = right {
+            self.nodes[right].parent = Some(h);
+        }
+        self.update_size(h);
+    }
+
+    /// Merges two treaps where every element of `left` precedes every
+    /// element of `right`, returning the merged root.
+    fn merge(&mut self, left: Option<usize>, right: Option<usize>) -> Option<usize> {
+        let (l, r) = match (left, right) {
+            (None, r) => return Self::detach(&mut self.nodes, r),
+            (l, None) => return Self::detach(&mut self.nodes, l),
+            (Some(l), Some(r)) => (l, r),
+        };
+
+        if self.nodes[l].priority > self.nodes[r].priority {
+            let right_child = self.nodes[l].right;
+            let merged = self.merge(right_child, Some(r));
+            self.set_right(l, merged);
+            self.nodes[l].parent = None;
+            Some(l)
+        } else {
+            let left_child = self.nodes[r].left;
+            let merged = self.merge(Some(l), left_child);
+            self.set_left(r, merged);
+            self.nodes[r].parent = None;
+            Some(r)
+        }
+    }
+
+    fn detach(nodes: &mut [TreapNode], h: Option<usize>) -> Option<usize> {
+        if let Some(h) = h {
+            nodes[h].parent = None;
+        }
+        h
+    }
+
+    /// Splits the treap rooted at `h` into the first `k` elements (by
+    /// in-order position) and the rest.
+    fn split(&mut self, h: Option<usize>, k: usize) -> (Option<usize>, Option<usize>) {
+        let h = match h {
+            None => return (None, None),
+            Some(h) => h,
+        };
+
+        let left_size = self.size(self.nodes[h].left);
+        if k <= left_size {
+            let left_child = self.nodes[h].left;
+            let (ll, lr) = self.split(left_child, k);
+            self.set_left(h, lr);
+            self.nodes[h].parent = None;
+            (Self::detach(&mut self.nodes, ll), Some(h))
+        } else {
+            let right_child = self.nodes[h].right;
+            let (rl, rr) = self.split(right_child, k - left_size - 1);
+            self.set_right(h, rl);
+            self.nodes[h].parent = None;
+            (Some(h), Self::detach(&mut self.nodes, rr))
+        }
+    }
+
+    fn insert_at(&mut self, pos: usize, value: usize) {
+        let h = self.alloc(value);
+        let (l, r) = self.split(self.root, pos);
+        let merged = self.merge(l, Some(h));
+        self.root = self.merge(merged, r);
+    }
+
+    /// Returns the in-order position of the node at `handle`, in O(log n)
+    /// expected: start from `handle`'s own left-subtree size, then for each
+    /// step up to the root where we came from a right child, add that
+    /// ancestor's left-subtree size plus one for the ancestor itself.
+    fn rank(&self, handle: usize) -> usize {
+        let mut r = self.size(self.nodes[handle].left);
+        let mut cur = handle;
+        while let Some(parent) = self.nodes[cur].parent {
+            if self.nodes[parent].right == Some(cur) {
+                r += self.size(self.nodes[parent].left) + 1;
+            }
+            cur = parent;
+        }
+        r
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the node at position `idx` (0-indexed) in the current order.
+    pub fn get(&self, idx: usize) -> usize {
+        let mut cur = self
+            .root
+            .unwrap_or_else(|| panic!("Index {} out of bounds for empty PLinkedList", idx));
+        let mut remaining = idx;
+        loop {
+            let left_size = self.size(self.nodes[cur].left);
+            match remaining.cmp(&left_size) {
+                std::cmp::Ordering::Less => cur = self.nodes[cur].left.unwrap(),
+                std::cmp::Ordering::Equal => return self.nodes[cur].value,
+                std::cmp::Ordering::Greater => {
+                    remaining -= left_size + 1;
+                    cur = self.nodes[cur]
+                        .right
+                        .unwrap_or_else(|| panic!("Index {} out of bounds for PLinkedList of length {}", idx, self.len()));
+                }
+            }
+        }
     }
-    
-    pub fn insert_after(&mut self, cur: usize, next: usize) {
-        let cur_succ = self.links[cur];
-        self.links[cur] = Some(next);
-        self.links[next] = cur_succ;
 
-        if cur == self.last {
-            self.last = next;
+    /// Returns a node for which `pred` holds and whose in-order successor
+    /// (if any) does not, i.e. a "crossover" position. Requires
+    /// `pred(first) == true` and `pred(last) == false`; `pred` need not be
+    /// monotonic over the rest of the sequence -- we only need *some*
+    /// crossover pair, not *the* rightmost one. Each visited node's subtree
+    /// covers a contiguous in-order rank range, so testing `pred` at that
+    /// node and recursing into its right subtree (on `true`) or left
+    /// subtree (on `false`) is exactly the lo/hi window a position-based
+    /// binary search would shrink, just driven by tree structure instead of
+    /// index arithmetic: a single O(log n) expected root-to-leaf descent,
+    /// rather than the O(log n) independent `get(mid)` calls (each its own
+    /// O(log n) descent) a position-based binary search would take.
+    pub fn rightmost_where(&self, mut pred: impl FnMut(usize) -> bool) -> usize {
+        let mut cur = self.root;
+        let mut best = None;
+        while let Some(h) = cur {
+            if pred(self.nodes[h].value) {
+                best = Some(self.nodes[h].value);
+                cur = self.nodes[h].right;
+            } else {
+                cur = self.nodes[h].left;
+            }
         }
+        best.expect("pred must hold for at least one element")
+    }
+
+    pub fn insert_after(&mut self, cur: usize, next: usize) {
+        let handle = self.handle_of[cur];
+        let pos = self.rank(handle) + 1;
+        self.insert_at(pos, next);
     }
 
     pub fn insert_at_end(&mut self, new: usize) {
-        self.links[self.last] = Some(new);
-        self.last = new;
+        let pos = self.len();
+        self.insert_at(pos, new);
     }
 
     pub fn insert_at_start(&mut self, new: usize) {
-        self.links[new] = Some(self.first);
-        self.first = new;
+        self.insert_at(0, new);
     }
 
     pub fn first(&self) -> usize {
-        self.first
+        self.get(0)
     }
 
     pub fn last(&self) -> usize {
-        self.last
+        self.get(self.len() - 1)
     }
 
-    pub fn iter(&self) -> PLinkedListIterator {
+    pub fn iter(&self) -> PLinkedListIterator<'_> {
         PLinkedListIterator::new(self)
     }
 }
 
 pub struct PLinkedListIterator<'a> {
     target: &'a PLinkedList,
-    cur: Option<usize>
+    idx: usize,
 }
 
 impl<'a> PLinkedListIterator<'a> {
     pub fn new(target: &'a PLinkedList) -> Self {
-        Self {
-            target,
-            cur: Some(target.first)
-        }
+        Self { target, idx: 0 }
     }
 }
 
@@ -77,9 +266,12 @@ impl<'a> Iterator for PLinkedListIterator<'a> {
     type Item = usize;
 
     fn next(&mut self) -> Option<usize> {
-        let rv = self.cur;
-        self.cur = self.target.links[rv?];
-        rv
+        if self.idx >= self.target.len() {
+            return None;
+        }
+        let rv = self.target.get(self.idx);
+        self.idx += 1;
+        Some(rv)
     }
 }
 
@@ -130,5 +322,68 @@ pub mod tests {
         let order = pll.iter().collect::<Vec<_>>();
         assert_eq!(order, vec![0, 2, 1]);
     }
-    
-}
\ No newline at end of file
+
+    /// `rightmost_where` only needs `pred(first) == true` and
+    /// `pred(last) == false`; in between it's allowed to be non-monotonic
+    /// (true, false, true, false, ...), which is the realistic case for the
+    /// tournament edge test it's used with in `fastpath_naive`. Check it
+    /// still returns a valid crossover (a node for which `pred` holds whose
+    /// in-order successor does not) rather than assuming sortedness.
+    #[test]
+    pub fn plinkedlist_rightmost_where_handles_non_monotonic_predicate() {
+        let mut pll = PLinkedList::new(4, 0);
+        pll.insert_at_end(1);
+        pll.insert_at_end(2);
+        pll.insert_at_end(3);
+        // Order is [0, 1, 2, 3]; pred is true at 0 and 2, false at 1 and 3.
+        let truthy = [0, 2];
+        let v = pll.rightmost_where(|x| truthy.contains(&x));
+        let order = pll.iter().collect::<Vec<_>>();
+        let pos = order.iter().position(|&x| x == v).unwrap();
+        assert!(truthy.contains(&v));
+        assert!(pos + 1 < order.len());
+        assert!(!truthy.contains(&order[pos + 1]));
+    }
+
+    #[test]
+    pub fn plinkedlist_indexed_access() {
+        let mut pll = PLinkedList::new(50, 0);
+        for i in 1..50 {
+            pll.insert_at_end(i);
+        }
+        for i in 0..50 {
+            assert_eq!(pll.get(i), i);
+        }
+    }
+
+    /// Randomized stress test mixing all three insert forms, checking `get`
+    /// and `rank` (via `insert_after`) stay consistent as the tree grows --
+    /// a sqrt(n)-block scheme would pass this too, but it's what would have
+    /// caught a broken split/merge/rank in the treap.
+    #[test]
+    pub fn plinkedlist_randomized_matches_reference_order() {
+        let n = 300;
+        let mut pll = PLinkedList::new(n, 0);
+        let mut reference = vec![0usize];
+        for v in 1..n {
+            let choice = v % 3;
+            if choice == 0 {
+                pll.insert_at_start(v);
+                reference.insert(0, v);
+            } else if choice == 1 {
+                pll.insert_at_end(v);
+                reference.push(v);
+            } else {
+                // Insert after whatever landed at the middle of the
+                // reference order so far.
+                let cur = reference[reference.len() / 2];
+                let pos = reference.iter().position(|&x| x == cur).unwrap();
+                reference.insert(pos + 1, v);
+                pll.insert_after(cur, v);
+            }
+        }
+        assert_eq!(pll.len(), n);
+        let order = pll.iter().collect::<Vec<_>>();
+        assert_eq!(order, reference);
+    }
+}